@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The user's most recent room, quality and format choices, so interactive prompts can
+/// offer them as defaults on the next launch.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Config {
+    pub room_id: Option<String>,
+    /// The platform `room_id` was resolved from (see `Platform::key`), so the next run
+    /// can reconstruct the exact platform instead of re-detecting it from the bare,
+    /// already-platform-stripped id.
+    #[serde(default)]
+    pub platform: Option<String>,
+    pub quality: Option<String>,
+    pub format: Option<String>,
+}
+
+impl Config {
+    /// Loads the saved config, falling back to an empty one if it doesn't exist or is
+    /// unreadable (e.g. corrupted by a previous version).
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)
+    }
+
+    fn path() -> Option<PathBuf> {
+        Some(dirs::config_dir()?.join("bili_live_src").join("config.json"))
+    }
+}
@@ -0,0 +1,24 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum Error {
+    Network(String),
+    InvalidResponse(String),
+    Offline,
+    Api(String),
+    UnsupportedUrl,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Network(msg) => write!(f, "网络请求出错，请稍后再试。({})", msg),
+            Self::InvalidResponse(msg) => write!(f, "接口返回格式错误：{}", msg),
+            Self::Offline => write!(f, "未开播。"),
+            Self::Api(msg) => write!(f, "请求出错：{}", msg),
+            Self::UnsupportedUrl => write!(f, "不支持的直播间地址。"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,143 @@
+use crate::error::Error;
+
+/// A single rendition offered by a master playlist.
+#[derive(Debug, Clone)]
+pub struct Variant {
+    pub bandwidth: u64,
+    pub resolution: Option<(u32, u32)>,
+    pub codecs: Option<String>,
+    pub url: String,
+}
+
+/// A summary of a media (leaf) playlist's segments.
+#[derive(Debug, Clone)]
+pub struct MediaSummary {
+    pub segment_count: usize,
+    pub total_duration: f64,
+    pub target_duration: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Playlist {
+    Master(Vec<Variant>),
+    Media(MediaSummary),
+}
+
+/// Fetches `url` and parses it as an HLS playlist.
+pub async fn fetch(url: &str) -> Result<Playlist, Error> {
+    let text = reqwest::get(url)
+        .await
+        .map_err(|e| Error::Network(e.to_string()))?
+        .text()
+        .await
+        .map_err(|e| Error::Network(e.to_string()))?;
+    Ok(parse(&text, url))
+}
+
+/// Parses `text` as either a master or media playlist. `base_url` is used to resolve
+/// variant URIs that are given relative to the playlist.
+pub fn parse(text: &str, base_url: &str) -> Playlist {
+    if text.contains("#EXT-X-STREAM-INF:") {
+        Playlist::Master(parse_master(text, base_url))
+    } else {
+        Playlist::Media(parse_media(text))
+    }
+}
+
+fn parse_master(text: &str, base_url: &str) -> Vec<Variant> {
+    let mut variants = Vec::new();
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        let Some(attrs) = line.strip_prefix("#EXT-X-STREAM-INF:") else {
+            continue;
+        };
+        // Tolerate a playlist with a stream-inf tag but no following uri.
+        let Some(uri) = lines.next() else { break };
+        let uri = uri.trim();
+        if uri.is_empty() {
+            continue;
+        }
+
+        variants.push(Variant {
+            bandwidth: attr(attrs, "BANDWIDTH").and_then(|v| v.parse().ok()).unwrap_or(0),
+            resolution: attr(attrs, "RESOLUTION").and_then(|v| {
+                let (w, h) = v.split_once('x')?;
+                Some((w.parse().ok()?, h.parse().ok()?))
+            }),
+            codecs: attr(attrs, "CODECS").map(|v| v.trim_matches('"').to_string()),
+            url: resolve_url(base_url, uri),
+        });
+    }
+    variants.sort_by_key(|v| v.bandwidth);
+    variants
+}
+
+fn parse_media(text: &str) -> MediaSummary {
+    let mut segment_count = 0;
+    let mut total_duration = 0.0;
+    let mut target_duration = None;
+
+    for line in text.lines() {
+        if let Some(rest) = line.strip_prefix("#EXT-X-TARGETDURATION:") {
+            target_duration = rest.trim().parse().ok();
+        } else if let Some(rest) = line.strip_prefix("#EXTINF:") {
+            if let Some((duration, _)) = rest.split_once(',') {
+                if let Ok(duration) = duration.trim().parse::<f64>() {
+                    total_duration += duration;
+                    segment_count += 1;
+                }
+            }
+        }
+    }
+
+    MediaSummary {
+        segment_count,
+        total_duration,
+        target_duration,
+    }
+}
+
+/// Extracts an `ATTR=value` pair from a `#EXT-X-STREAM-INF:` attribute list, respecting
+/// quoted values that may themselves contain commas (e.g. `CODECS="avc1.64001f,mp4a.40.2"`).
+fn attr<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let mut search_from = 0;
+    loop {
+        let key_start = search_from + attrs[search_from..].find(name)?;
+        let at_boundary = key_start == 0 || attrs.as_bytes()[key_start - 1] == b',';
+        let rest = &attrs[key_start..];
+        if at_boundary {
+            if let Some(after_eq) = rest.strip_prefix(name).and_then(|s| s.strip_prefix('=')) {
+                let end = if let Some(quoted) = after_eq.strip_prefix('"') {
+                    quoted.find('"').map(|i| i + 2).unwrap_or(after_eq.len())
+                } else {
+                    after_eq.find(',').unwrap_or(after_eq.len())
+                };
+                return Some(&after_eq[..end]);
+            }
+        }
+        search_from = key_start + name.len();
+    }
+}
+
+fn resolve_url(base_url: &str, uri: &str) -> String {
+    if uri.starts_with("http://") || uri.starts_with("https://") {
+        return uri.to_string();
+    }
+    if let Some(path) = uri.strip_prefix('/') {
+        return format!("{}/{}", authority(base_url), path);
+    }
+    match base_url.rsplit_once('/') {
+        Some((base, _)) => format!("{}/{}", base, uri),
+        None => uri.to_string(),
+    }
+}
+
+/// The scheme+host portion of `url`, e.g. `https://cdn.example.com` out of
+/// `https://cdn.example.com/live/room/index.m3u8`.
+fn authority(url: &str) -> &str {
+    let after_scheme = url.find("://").map(|i| i + 3).unwrap_or(0);
+    match url[after_scheme..].find('/') {
+        Some(i) => &url[..after_scheme + i],
+        None => url,
+    }
+}
@@ -1,47 +1,135 @@
 #![allow(dead_code)]
+mod config;
+mod error;
+mod hls;
+mod recorder;
+mod source;
+
 use clap::Parser;
-use regex::Regex;
-use serde_json::{Error, Value};
+use config::Config;
+use error::Error;
+use source::Platform;
 use std::io::prelude::*;
+use std::path::PathBuf;
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() {
+    if let Err(e) = run().await {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    env_logger::init();
+
     let Args {
         format,
         quality,
         room_id,
+        output,
+        codec,
+        protocol,
+        no_save,
     } = Args::parse();
-    let is_cli = format.is_some() || quality.is_some() || room_id.is_some();
+    let is_cli = format.is_some()
+        || quality.is_some()
+        || room_id.is_some()
+        || output.is_some()
+        || codec.is_some()
+        || protocol.is_some();
+
+    let saved = Config::load();
 
     let format = Format::from_str(&format.unwrap_or("".to_string())).ok();
     let quality = Quality::from_str(&quality.unwrap_or("".to_string())).ok();
+    let codec = Codec::from_str(&codec.unwrap_or("".to_string())).ok();
+    let protocol = Protocol::from_str(&protocol.unwrap_or("".to_string())).ok();
 
-    let room_id = match room_id {
-        Some(id) => id,
-        None => read_room_id(),
+    if room_id.as_ref().is_some_and(|ids| ids.len() > 1) {
+        let ids = room_id.unwrap();
+        let quality = quality
+            .or_else(|| saved.quality.as_deref().and_then(|q| Quality::from_str(q).ok()))
+            .unwrap_or(Quality::Original);
+        let codec = codec.unwrap_or(Codec::Avc);
+        let protocol = protocol.unwrap_or(Protocol::HttpStream);
+        let format = format
+            .or_else(|| saved.format.as_deref().and_then(|f| Format::from_str(f).ok()))
+            .unwrap_or(Format::M3u8);
+
+        if !no_save {
+            Config {
+                room_id: Some(ids.join(",")),
+                platform: None,
+                quality: Some(quality.as_str().to_string()),
+                format: Some(format.as_str().to_string()),
+            }
+            .save()?;
+        }
+
+        fetch_many(ids, quality, codec, protocol, format).await;
+        return Ok(());
+    }
+    let room_id = room_id.and_then(|mut ids| ids.pop());
+
+    let (platform, room_id) = match room_id {
+        Some(id) => Platform::detect(&id).ok_or("直播间地址或房间号格式不正确。")?,
+        None => read_room_id(saved.platform.as_deref().zip(saved.room_id.as_deref())),
     };
     let quality = match quality {
         Some(q) => q,
-        None => read_quality(),
+        None => {
+            let available = platform.accept_qualities(&room_id).await?;
+            let default = saved.quality.as_deref().and_then(|q| Quality::from_str(q).ok());
+            read_quality(&available, default)
+        }
+    };
+    let codec = match codec {
+        Some(c) => c,
+        None => read_codec(),
+    };
+    let protocol = match protocol {
+        Some(p) => p,
+        None => read_protocol(),
     };
     let format = match format {
         Some(f) => f,
-        None => read_format(),
+        None => {
+            let default = saved.format.as_deref().and_then(|f| Format::from_str(f).ok());
+            read_format(default)
+        }
     };
 
+    if !no_save {
+        Config {
+            room_id: Some(room_id.clone()),
+            platform: Some(platform.key().to_string()),
+            quality: Some(quality.as_str().to_string()),
+            format: Some(format.as_str().to_string()),
+        }
+        .save()?;
+    }
+
     if !is_cli {
         println!("正在获取直播源...\n");
     }
-    let stream = fetch_stream(room_id, quality).await?;
-    let urls = parse_stream(stream);
+    let urls = platform.fetch(&room_id, quality, codec, protocol).await?;
 
     let urls = urls
         .iter()
         .filter(|u| u.contains(&format.value()))
         .collect::<Vec<&String>>();
 
-    for url in urls.iter() {
-        println!("{}", url.trim_matches('"'));
+    match output {
+        Some(path) => {
+            let url = urls.first().ok_or("没有可用的直播源。")?.trim_matches('"');
+            println!("正在录制到 {}...", path.display());
+            recorder::record(url, &format, &path).await?;
+            println!("录制完成。");
+        }
+        None => {
+            print_urls(&urls, &format, is_cli).await;
+        }
     }
     if !is_cli {
         pause();
@@ -52,12 +140,151 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
 #[derive(Parser, Debug)]
 struct Args {
-    #[arg(short, long)]
-    room_id: Option<u32>,
+    /// Room id(s) or urls. Accepts a comma-separated list or the flag repeated to fetch
+    /// multiple rooms concurrently.
+    #[arg(short, long, value_delimiter = ',')]
+    room_id: Option<Vec<String>>,
     #[arg(short, long)]
     quality: Option<String>,
     #[arg(short, long)]
     format: Option<String>,
+    /// Record the selected stream to this path instead of printing the url.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// avc/h264 or hevc/h265.
+    #[arg(short, long)]
+    codec: Option<String>,
+    /// http_stream/stream or http_hls/hls.
+    #[arg(short, long)]
+    protocol: Option<String>,
+    /// Don't persist this run's room/quality/format choices for next time.
+    #[arg(long)]
+    no_save: bool,
+}
+
+/// How many rooms to fetch at once, so a large batch doesn't hammer every platform's API
+/// simultaneously.
+const MAX_CONCURRENT_ROOMS: usize = 4;
+
+/// Fetches multiple rooms concurrently, printing results grouped per room and skipping
+/// offline ones instead of aborting the whole batch.
+async fn fetch_many(ids: Vec<String>, quality: Quality, codec: Codec, protocol: Protocol, format: Format) {
+    use futures::stream::{self, StreamExt};
+
+    let results = stream::iter(ids)
+        .map(|input| async move {
+            match Platform::detect(&input) {
+                Some((platform, room_id)) => {
+                    log::debug!("fetching room {}", room_id);
+                    let result = platform.fetch(&room_id, quality, codec, protocol).await;
+                    (room_id, result)
+                }
+                None => (input, Err(Error::UnsupportedUrl)),
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_ROOMS)
+        .collect::<Vec<_>>()
+        .await;
+
+    for (room_id, result) in results {
+        println!("== 房间 {} ==", room_id);
+        match result {
+            Ok(urls) => {
+                let matched = urls
+                    .iter()
+                    .filter(|u| u.contains(&format.value()))
+                    .collect::<Vec<&String>>();
+                if matched.is_empty() {
+                    println!("没有匹配所选画质/编码/协议/格式的直播源。");
+                } else {
+                    for url in matched {
+                        println!("{}", url.trim_matches('"'));
+                    }
+                }
+            }
+            Err(Error::Offline) => {
+                log::debug!("room {} is offline, skipping", room_id);
+                println!("未开播，已跳过。");
+            }
+            Err(e) => {
+                log::warn!("room {} failed: {}", room_id, e);
+                println!("{}", e);
+            }
+        }
+    }
+}
+
+/// Prints the selected stream urls. For m3u8, parses the playlist first so the user can
+/// pick an exact rendition instead of trusting the raw url. The interactive variant
+/// picker only runs when `is_cli` is false — a fully flag-driven invocation has no
+/// terminal to prompt on and must never block waiting for input.
+async fn print_urls(urls: &[&String], format: &Format, is_cli: bool) {
+    if urls.is_empty() {
+        println!("没有匹配所选画质/编码/协议/格式的直播源。");
+        return;
+    }
+
+    if matches!(format, Format::M3u8) {
+        if let Some(url) = urls.first() {
+            let url = url.trim_matches('"');
+            if let Ok(playlist) = hls::fetch(url).await {
+                match playlist {
+                    hls::Playlist::Master(variants) if !variants.is_empty() => {
+                        let chosen = if is_cli {
+                            // Playlist is sorted ascending by bandwidth; last is highest.
+                            variants.last().unwrap()
+                        } else {
+                            read_variant(&variants)
+                        };
+                        println!("{}", chosen.url);
+                        return;
+                    }
+                    hls::Playlist::Media(summary) => {
+                        println!(
+                            "媒体播放列表：{} 个分片，总时长 {:.1}s{}",
+                            summary.segment_count,
+                            summary.total_duration,
+                            summary
+                                .target_duration
+                                .map(|d| format!("，目标分片时长 {:.1}s", d))
+                                .unwrap_or_default(),
+                        );
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    for url in urls.iter() {
+        println!("{}", url.trim_matches('"'));
+    }
+}
+
+fn read_variant(variants: &[hls::Variant]) -> &hls::Variant {
+    let mut result = None;
+    while result.is_none() {
+        println!("\n可用分辨率（按带宽排序）:");
+        for (i, v) in variants.iter().enumerate() {
+            let resolution = v
+                .resolution
+                .map(|(w, h)| format!("{}x{}", w, h))
+                .unwrap_or_else(|| "未知分辨率".to_string());
+            let codecs = v.codecs.as_deref().unwrap_or("未知编码");
+            println!("{}. {} - {} - {} bps", i + 1, resolution, codecs, v.bandwidth);
+        }
+        println!();
+
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line).unwrap();
+        result = line
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| i.checked_sub(1))
+            .and_then(|i| variants.get(i));
+    }
+    result.unwrap()
 }
 
 fn pause() {
@@ -68,65 +295,135 @@ fn pause() {
     let _ = stdin.read(&mut [0u8]).unwrap();
 }
 
-fn read_room_id() -> u32 {
-    let mut line = String::new();
-    let mut room_id: Result<u32, &'static str> = Err("init");
-    while room_id.is_err() {
-        println!("输入房间号或直播间地址: ");
+/// `default` is the saved `(platform_key, room_id)` pair, if any. The platform is
+/// reconstructed directly from `platform_key` via `Platform::from_key` rather than
+/// re-running `Platform::detect` on `room_id`, since `room_id` has already had its
+/// platform-qualifying prefix stripped off by a previous `detect` call and would
+/// otherwise risk matching a different platform (e.g. Bilibili's bare-digits regex).
+fn read_room_id(default: Option<(&str, &str)>) -> (Platform, String) {
+    let mut result: Option<(Platform, String)> = None;
+    while result.is_none() {
+        let mut line = String::new();
+        match default {
+            Some((_, d)) => println!("输入房间号或直播间地址 (默认: {}): ", d),
+            None => println!("输入房间号或直播间地址: "),
+        }
         std::io::stdin().read_line(&mut line).unwrap();
 
-        let re = Regex::new(r"(http[s]?://)?(live.bilibili.com/)?(\d+)").unwrap();
-        let caps = re.captures(line.trim());
-
-        room_id = match caps {
-            None => Err("直播间地址或房间号格式不正确。"),
-            Some(caps) => Ok(caps.get(3).unwrap().as_str().parse::<u32>().unwrap()),
+        let input = line.trim();
+        result = if input.is_empty() {
+            default.and_then(|(key, id)| Platform::from_key(key).map(|p| (p, id.to_string())))
+        } else {
+            Platform::detect(input)
         };
-        if let Err(e) = room_id {
-            println!("{}", e);
+        if result.is_none() {
+            println!("直播间地址或房间号格式不正确。");
         }
     }
-    room_id.unwrap()
+    result.unwrap()
 }
 
-fn read_quality() -> Quality {
+fn read_quality(available: &[Quality], default: Option<Quality>) -> Quality {
     let mut line = String::new();
-    let mut result: Result<Quality, &'static str> = Err("init");
+    let mut result: Option<Quality> = None;
 
-    while result.is_err() {
+    while result.is_none() {
         println!("\n选择画质:");
-        println!("1. 流畅");
-        println!("2. 原画\n");
+        for (i, q) in available.iter().enumerate() {
+            println!("{}. {}", i + 1, q.label());
+        }
+        if let Some(d) = default {
+            println!("(默认: {})", d.label());
+        }
+        println!();
+
+        std::io::stdin().read_line(&mut line).unwrap();
+        let input = line.trim();
+        result = if input.is_empty() {
+            default.filter(|d| available.contains(d))
+        } else {
+            input
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| i.checked_sub(1))
+                .and_then(|i| available.get(i))
+                .copied()
+        };
+        line.clear();
+    }
+    result.unwrap()
+}
+
+fn read_codec() -> Codec {
+    let mut line = String::new();
+    let mut result: Result<Codec, &'static str> = Err("init");
+
+    while result.is_err() {
+        println!("\n选择编码:");
+        println!("1. AVC (H.264)");
+        println!("2. HEVC (H.265)\n");
 
         std::io::stdin().read_line(&mut line).unwrap();
         result = match line.trim().parse::<u32>() {
-            Ok(1) => Ok(Quality::Low),
-            Ok(2) => Ok(Quality::High),
+            Ok(1) => Ok(Codec::Avc),
+            Ok(2) => Ok(Codec::Hevc),
             _ => Err(""),
         };
+        line.clear();
     }
     result.unwrap()
 }
 
-fn read_format() -> Format {
+fn read_protocol() -> Protocol {
     let mut line = String::new();
-    let mut result: Result<Format, &'static str> = Err("init");
+    let mut result: Result<Protocol, &'static str> = Err("init");
 
     while result.is_err() {
-        println!("\n选择格式:");
-        println!("1. m3u8");
-        println!("2. flv\n");
+        println!("\n选择传输协议:");
+        println!("1. HTTP_STREAM");
+        println!("2. HTTP_HLS\n");
 
         std::io::stdin().read_line(&mut line).unwrap();
         result = match line.trim().parse::<u32>() {
-            Ok(1) => Ok(Format::M3u8),
-            Ok(2) => Ok(Format::Flv),
+            Ok(1) => Ok(Protocol::HttpStream),
+            Ok(2) => Ok(Protocol::HttpHls),
             _ => Err(""),
         };
+        line.clear();
     }
     result.unwrap()
 }
 
+fn read_format(default: Option<Format>) -> Format {
+    let mut line = String::new();
+    let mut result: Option<Format> = None;
+
+    while result.is_none() {
+        println!("\n选择格式:");
+        println!("1. m3u8");
+        println!("2. flv");
+        if let Some(d) = &default {
+            println!("(默认: {})", d.as_str());
+        }
+        println!();
+
+        std::io::stdin().read_line(&mut line).unwrap();
+        let input = line.trim();
+        result = if input.is_empty() {
+            default
+        } else {
+            match input.parse::<u32>() {
+                Ok(1) => Some(Format::M3u8),
+                Ok(2) => Some(Format::Flv),
+                _ => None,
+            }
+        };
+        line.clear();
+    }
+    result.unwrap()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Format {
     M3u8,
     Flv,
@@ -145,97 +442,125 @@ impl Format {
             Self::Flv => "flv".to_string(),
         }
     }
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::M3u8 => "m3u8",
+            Self::Flv => "flv",
+        }
+    }
 }
 
-enum Quality {
-    Low,
-    High,
+/// Bilibili's `qn` stream quality tiers, as advertised by `accept_qn`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Quality {
+    Fluent,
+    HD,
+    UltraHD,
+    UltraHD2,
+    BlueRay,
+    Original,
 }
 impl Quality {
     fn from_str(s: &str) -> Result<Self, &'static str> {
         match s {
-            "low" => Ok(Self::Low),
-            "high" => Ok(Self::High),
+            "fluent" | "low" => Ok(Self::Fluent),
+            "hd" => Ok(Self::HD),
+            "uhd" => Ok(Self::UltraHD),
+            "uhd2" => Ok(Self::UltraHD2),
+            "blueray" => Ok(Self::BlueRay),
+            "original" | "high" => Ok(Self::Original),
             _ => Err(""),
         }
     }
 
-    fn value(&self) -> u32 {
-        match self {
-            Self::Low => 0,
-            Self::High => 10000,
+    fn from_qn(qn: u32) -> Option<Self> {
+        match qn {
+            80 => Some(Self::Fluent),
+            150 => Some(Self::HD),
+            250 => Some(Self::UltraHD),
+            400 => Some(Self::UltraHD2),
+            10000 => Some(Self::Original),
+            20000 => Some(Self::BlueRay),
+            _ => None,
         }
     }
-}
 
-async fn fetch_stream(room_id: u32, qn: Quality) -> Result<Vec<Value>, &'static str> {
-    let base_url = "https://api.live.bilibili.com/xlive/web-room/v2/index/getRoomPlayInfo";
-    let url = format!(
-        "{}?qn={}&protocol=0,1&format=0,1,2&codec=0,1&room_id={}",
-        base_url,
-        qn.value(),
-        room_id
-    );
-    let resp = reqwest::get(url).await;
-    if resp.is_err() {
-        return Err("网络请求出错，请稍后再试。");
+    pub(crate) fn value(&self) -> u32 {
+        match self {
+            Self::Fluent => 80,
+            Self::HD => 150,
+            Self::UltraHD => 250,
+            Self::UltraHD2 => 400,
+            Self::Original => 10000,
+            Self::BlueRay => 20000,
+        }
     }
-    let resp = resp.unwrap();
-    let resp = resp.text().await.unwrap();
-    let resp: Result<Value, Error> = serde_json::from_str(&resp);
-    if resp.is_err() {
-        return Err("接口返回格式错误");
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Fluent => "流畅",
+            Self::HD => "高清",
+            Self::UltraHD => "超清",
+            Self::UltraHD2 => "超清（高码率）",
+            Self::BlueRay => "蓝光",
+            Self::Original => "原画",
+        }
     }
-    let resp = resp.unwrap();
 
-    let code = &resp["code"].as_i64().unwrap();
-    if *code != 0 {
-        return Err("请求出错。");
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Fluent => "fluent",
+            Self::HD => "hd",
+            Self::UltraHD => "uhd",
+            Self::UltraHD2 => "uhd2",
+            Self::BlueRay => "blueray",
+            Self::Original => "original",
+        }
     }
+}
 
-    let live_status = &resp["data"]["live_status"];
-    if live_status.as_i64().unwrap() == 0 {
-        return Err("未开播。");
+/// The video codec a room's stream is encoded with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Codec {
+    Avc,
+    Hevc,
+}
+impl Codec {
+    fn from_str(s: &str) -> Result<Self, &'static str> {
+        match s {
+            "avc" | "h264" => Ok(Self::Avc),
+            "hevc" | "h265" => Ok(Self::Hevc),
+            _ => Err(""),
+        }
     }
 
-    let stream = &resp["data"]["playurl_info"]["playurl"]["stream"]
-        .as_array()
-        .unwrap();
+    pub(crate) fn value(&self) -> u32 {
+        match self {
+            Self::Avc => 0,
+            Self::Hevc => 1,
+        }
+    }
+}
 
-    Ok(stream.to_owned().to_owned())
+/// The transport a room's stream is delivered over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Protocol {
+    HttpStream,
+    HttpHls,
 }
+impl Protocol {
+    fn from_str(s: &str) -> Result<Self, &'static str> {
+        match s {
+            "http_stream" | "stream" => Ok(Self::HttpStream),
+            "http_hls" | "hls" => Ok(Self::HttpHls),
+            _ => Err(""),
+        }
+    }
 
-fn parse_stream(stream: Vec<Value>) -> Vec<String> {
-    stream
-        .iter()
-        .flat_map(|s| {
-            s["format"]
-                .as_array()
-                .unwrap()
-                .iter()
-                .flat_map(|f| {
-                    f["codec"]
-                        .as_array()
-                        .unwrap()
-                        .iter()
-                        .flat_map(|c| {
-                            c["url_info"]
-                                .as_array()
-                                .unwrap()
-                                .iter()
-                                .map(|i| {
-                                    format!(
-                                        "{}{}{}",
-                                        i["host"].to_string().trim_matches('"'),
-                                        c["base_url"].to_string().trim_matches('"'),
-                                        i["extra"].to_string().trim_matches('"')
-                                    )
-                                })
-                                .collect::<Vec<String>>()
-                        })
-                        .collect::<Vec<String>>()
-                })
-                .collect::<Vec<String>>()
-        })
-        .collect::<Vec<String>>()
+    pub(crate) fn value(&self) -> u32 {
+        match self {
+            Self::HttpStream => 0,
+            Self::HttpHls => 1,
+        }
+    }
 }
@@ -0,0 +1,80 @@
+use crate::error::Error;
+use crate::Format;
+use futures::StreamExt;
+use std::path::Path;
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+
+/// Live streams frequently drop mid-broadcast, so a single network hiccup shouldn't
+/// abort the whole recording.
+const MAX_DOWNLOAD_ATTEMPTS: u32 = 5;
+
+/// Records `url` to `output`, choosing the download strategy based on `format`.
+/// Writes to a `.part` file while in progress and renames it on completion so an
+/// interrupted recording is never mistaken for a finished one.
+pub async fn record(url: &str, format: &Format, output: &Path) -> Result<(), Error> {
+    let part_path = output.with_extension(match output.extension() {
+        Some(ext) => format!("{}.part", ext.to_string_lossy()),
+        None => "part".to_string(),
+    });
+
+    let mut last_err = None;
+    for attempt in 1..=MAX_DOWNLOAD_ATTEMPTS {
+        let result = match format {
+            Format::Flv => record_flv(url, &part_path).await,
+            Format::M3u8 => record_m3u8(url, &part_path).await,
+        };
+        match result {
+            Ok(()) => {
+                tokio::fs::rename(&part_path, output)
+                    .await
+                    .map_err(|e| Error::Network(e.to_string()))?;
+                return Ok(());
+            }
+            Err(e) => {
+                log::debug!("recording attempt {}/{} failed: {}", attempt, MAX_DOWNLOAD_ATTEMPTS, e);
+                println!("录制中断（第 {}/{} 次尝试）：{}", attempt, MAX_DOWNLOAD_ATTEMPTS, e);
+                last_err = Some(e);
+            }
+        }
+    }
+    Err(last_err.unwrap_or(Error::Network("下载失败".to_string())))
+}
+
+async fn record_flv(url: &str, part_path: &Path) -> Result<(), Error> {
+    let resp = reqwest::get(url)
+        .await
+        .map_err(|e| Error::Network(e.to_string()))?;
+    let mut stream = resp.bytes_stream();
+    let mut file = File::create(part_path)
+        .await
+        .map_err(|e| Error::Network(e.to_string()))?;
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| Error::Network(e.to_string()))?;
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+    }
+    Ok(())
+}
+
+async fn record_m3u8(url: &str, part_path: &Path) -> Result<(), Error> {
+    let status = tokio::process::Command::new("ffmpeg")
+        .args([
+            "-y",
+            "-i",
+            url,
+            "-c",
+            "copy",
+            part_path.to_str().unwrap_or("output.part"),
+        ])
+        .status()
+        .await
+        .map_err(|e| Error::Network(format!("无法启动 ffmpeg：{}", e)))?;
+
+    if !status.success() {
+        return Err(Error::Network(format!("ffmpeg 退出码：{}", status)));
+    }
+    Ok(())
+}
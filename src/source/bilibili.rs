@@ -0,0 +1,141 @@
+use super::LiveSource;
+use crate::error::Error;
+use crate::{Codec, Protocol, Quality};
+use async_trait::async_trait;
+use regex::Regex;
+use serde_json::Value;
+
+pub struct Bilibili;
+
+#[async_trait]
+impl LiveSource for Bilibili {
+    async fn fetch(
+        &self,
+        room_id: &str,
+        quality: Quality,
+        codec: Codec,
+        protocol: Protocol,
+    ) -> Result<Vec<String>, Error> {
+        let room_id: u32 = room_id
+            .parse()
+            .map_err(|_| Error::InvalidResponse("房间号格式不正确".to_string()))?;
+        let stream = fetch_stream(room_id, quality.value()).await?;
+        Ok(parse_stream(stream, codec.value(), protocol.value()))
+    }
+
+    async fn accept_qualities(&self, room_id: &str) -> Result<Vec<Quality>, Error> {
+        let room_id: u32 = room_id
+            .parse()
+            .map_err(|_| Error::InvalidResponse("房间号格式不正确".to_string()))?;
+        let stream = fetch_stream(room_id, Quality::Fluent.value()).await?;
+        let qns = stream
+            .iter()
+            .flat_map(|s| s["format"].as_array().cloned().unwrap_or_default())
+            .flat_map(|f| f["codec"].as_array().cloned().unwrap_or_default())
+            .flat_map(|c| {
+                c["accept_qn"]
+                    .as_array()
+                    .cloned()
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|q| q.as_i64())
+                    .collect::<Vec<i64>>()
+            })
+            .collect::<Vec<i64>>();
+
+        let mut qualities = qns
+            .into_iter()
+            .filter_map(|qn| Quality::from_qn(qn as u32))
+            .collect::<Vec<Quality>>();
+        qualities.sort_by_key(|q| q.value());
+        qualities.dedup();
+
+        if qualities.is_empty() {
+            qualities = vec![Quality::Fluent, Quality::Original];
+        }
+        Ok(qualities)
+    }
+
+    fn matches_url(url: &str) -> Option<String> {
+        let re = Regex::new(r"(http[s]?://)?(live\.bilibili\.com/)?(\d+)").unwrap();
+        let caps = re.captures(url.trim())?;
+        Some(caps.get(3)?.as_str().to_string())
+    }
+}
+
+async fn fetch_stream(room_id: u32, qn: u32) -> Result<Vec<Value>, Error> {
+    let base_url = "https://api.live.bilibili.com/xlive/web-room/v2/index/getRoomPlayInfo";
+    let url = format!(
+        "{}?qn={}&protocol=0,1&format=0,1,2&codec=0,1&room_id={}",
+        base_url, qn, room_id
+    );
+    log::debug!("GET {}", url);
+    let resp = reqwest::get(url)
+        .await
+        .map_err(|e| Error::Network(e.to_string()))?;
+    log::debug!("room {} getRoomPlayInfo -> {}", room_id, resp.status());
+    let resp = resp.text().await.map_err(|e| Error::Network(e.to_string()))?;
+    let resp: Value =
+        serde_json::from_str(&resp).map_err(|e| Error::InvalidResponse(e.to_string()))?;
+
+    let code = resp["code"]
+        .as_i64()
+        .ok_or_else(|| Error::InvalidResponse("缺少 code 字段".to_string()))?;
+    if code != 0 {
+        log::debug!("room {} returned non-zero code {}", room_id, code);
+        return Err(Error::Api(format!("code={}", code)));
+    }
+
+    let live_status = &resp["data"]["live_status"];
+    if live_status.as_i64().unwrap_or(0) == 0 {
+        return Err(Error::Offline);
+    }
+
+    let stream = resp["data"]["playurl_info"]["playurl"]["stream"]
+        .as_array()
+        .ok_or_else(|| Error::InvalidResponse("缺少 stream 字段".to_string()))?;
+
+    Ok(stream.to_owned())
+}
+
+fn parse_stream(stream: Vec<Value>, codec: u32, protocol: u32) -> Vec<String> {
+    stream
+        .iter()
+        .filter(|s| {
+            s["protocol_name"].as_str().is_none_or(|p| {
+                let want = if protocol == 0 { "http_stream" } else { "http_hls" };
+                p == want
+            })
+        })
+        .flat_map(|s| {
+            s["format"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .flat_map(|f| {
+                    f["codec"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .filter(|c| c["codec_id"].as_i64().is_none_or(|id| id as u32 == codec))
+                        .flat_map(|c| {
+                            c["url_info"]
+                                .as_array()
+                                .unwrap()
+                                .iter()
+                                .map(|i| {
+                                    format!(
+                                        "{}{}{}",
+                                        i["host"].to_string().trim_matches('"'),
+                                        c["base_url"].to_string().trim_matches('"'),
+                                        i["extra"].to_string().trim_matches('"')
+                                    )
+                                })
+                                .collect::<Vec<String>>()
+                        })
+                        .collect::<Vec<String>>()
+                })
+                .collect::<Vec<String>>()
+        })
+        .collect::<Vec<String>>()
+}
@@ -0,0 +1,51 @@
+use super::LiveSource;
+use crate::error::Error;
+use crate::{Codec, Protocol, Quality};
+use async_trait::async_trait;
+use regex::Regex;
+use serde_json::Value;
+
+pub struct Douyin;
+
+#[async_trait]
+impl LiveSource for Douyin {
+    async fn fetch(
+        &self,
+        room_id: &str,
+        _quality: Quality,
+        _codec: Codec,
+        _protocol: Protocol,
+    ) -> Result<Vec<String>, Error> {
+        let url = format!(
+            "https://webcast.amemv.com/webcast/room/reflow/info/?room_id={}",
+            room_id
+        );
+        log::debug!("GET {}", url);
+        let resp = reqwest::get(url)
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+        log::debug!("room {} reflow/info -> {}", room_id, resp.status());
+        let resp = resp.text().await.map_err(|e| Error::Network(e.to_string()))?;
+        let resp: Value =
+            serde_json::from_str(&resp).map_err(|e| Error::InvalidResponse(e.to_string()))?;
+
+        let status = resp["data"]["room"]["status"].as_i64().unwrap_or(4);
+        if status != 2 {
+            log::debug!("room {} status {}", room_id, status);
+            return Err(Error::Offline);
+        }
+
+        let pull_url = &resp["data"]["room"]["stream_url"]["hls_pull_url"];
+        let url = pull_url
+            .as_str()
+            .ok_or_else(|| Error::InvalidResponse("缺少 hls_pull_url 字段".to_string()))?;
+
+        Ok(vec![url.to_string()])
+    }
+
+    fn matches_url(url: &str) -> Option<String> {
+        let re = Regex::new(r"live\.douyin\.com/(\d+)").unwrap();
+        let caps = re.captures(url.trim())?;
+        Some(caps.get(1)?.as_str().to_string())
+    }
+}
@@ -0,0 +1,51 @@
+use super::LiveSource;
+use crate::error::Error;
+use crate::{Codec, Protocol, Quality};
+use async_trait::async_trait;
+use regex::Regex;
+use serde_json::Value;
+
+pub struct Douyu;
+
+#[async_trait]
+impl LiveSource for Douyu {
+    async fn fetch(
+        &self,
+        room_id: &str,
+        _quality: Quality,
+        _codec: Codec,
+        _protocol: Protocol,
+    ) -> Result<Vec<String>, Error> {
+        let url = format!(
+            "https://www.douyu.com/betard/{}?__ajax_direct=1",
+            room_id
+        );
+        log::debug!("GET {}", url);
+        let resp = reqwest::get(url)
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+        log::debug!("room {} betard -> {}", room_id, resp.status());
+        let resp = resp.text().await.map_err(|e| Error::Network(e.to_string()))?;
+        let resp: Value =
+            serde_json::from_str(&resp).map_err(|e| Error::InvalidResponse(e.to_string()))?;
+
+        let show_status = resp["room"]["show_status"].as_i64().unwrap_or(0);
+        if show_status != 1 {
+            log::debug!("room {} show_status {}", room_id, show_status);
+            return Err(Error::Offline);
+        }
+
+        let flv_url = resp["room"]["hls_url"]
+            .as_str()
+            .or_else(|| resp["room"]["flv_url"].as_str())
+            .ok_or_else(|| Error::InvalidResponse("缺少直播源字段".to_string()))?;
+
+        Ok(vec![flv_url.to_string()])
+    }
+
+    fn matches_url(url: &str) -> Option<String> {
+        let re = Regex::new(r"douyu\.com/(\d+)").unwrap();
+        let caps = re.captures(url.trim())?;
+        Some(caps.get(1)?.as_str().to_string())
+    }
+}
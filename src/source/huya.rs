@@ -0,0 +1,52 @@
+use super::LiveSource;
+use crate::error::Error;
+use crate::{Codec, Protocol, Quality};
+use async_trait::async_trait;
+use regex::Regex;
+use serde_json::Value;
+
+pub struct Huya;
+
+#[async_trait]
+impl LiveSource for Huya {
+    async fn fetch(
+        &self,
+        room_id: &str,
+        _quality: Quality,
+        _codec: Codec,
+        _protocol: Protocol,
+    ) -> Result<Vec<String>, Error> {
+        let url = format!("https://www.huya.com/{}", room_id);
+        log::debug!("GET {}", url);
+        let resp = reqwest::get(url)
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+        log::debug!("room {} page -> {}", room_id, resp.status());
+        let html = resp.text().await.map_err(|e| Error::Network(e.to_string()))?;
+
+        let re = Regex::new(r"var\s+TT_ROOM_DATA\s*=\s*(\{.*?\});").unwrap();
+        let caps = re
+            .captures(&html)
+            .ok_or_else(|| Error::InvalidResponse("未找到直播间数据".to_string()))?;
+        let data: Value = serde_json::from_str(&caps[1])
+            .map_err(|e| Error::InvalidResponse(e.to_string()))?;
+
+        let state = data["state"].as_str().unwrap_or("");
+        if state != "ON" {
+            log::debug!("room {} state {}", room_id, state);
+            return Err(Error::Offline);
+        }
+
+        let stream_url = data["stream"]["flv"]
+            .as_str()
+            .ok_or_else(|| Error::InvalidResponse("缺少 flv 字段".to_string()))?;
+
+        Ok(vec![stream_url.to_string()])
+    }
+
+    fn matches_url(url: &str) -> Option<String> {
+        let re = Regex::new(r"huya\.com/([A-Za-z0-9_]+)").unwrap();
+        let caps = re.captures(url.trim())?;
+        Some(caps.get(1)?.as_str().to_string())
+    }
+}
@@ -0,0 +1,59 @@
+use super::LiveSource;
+use crate::error::Error;
+use crate::{Codec, Protocol, Quality};
+use async_trait::async_trait;
+use regex::Regex;
+use serde_json::Value;
+
+pub struct Kuaishou;
+
+#[async_trait]
+impl LiveSource for Kuaishou {
+    async fn fetch(
+        &self,
+        room_id: &str,
+        _quality: Quality,
+        _codec: Codec,
+        _protocol: Protocol,
+    ) -> Result<Vec<String>, Error> {
+        let url = format!("https://live.kuaishou.com/u/{}", room_id);
+        log::debug!("GET {}", url);
+        let resp = reqwest::get(url)
+            .await
+            .map_err(|e| Error::Network(e.to_string()))?;
+        log::debug!("room {} page -> {}", room_id, resp.status());
+        let html = resp.text().await.map_err(|e| Error::Network(e.to_string()))?;
+
+        let re = Regex::new(r#"window\.__INITIAL_STATE__\s*=\s*(\{.*?\});"#).unwrap();
+        let caps = re
+            .captures(&html)
+            .ok_or_else(|| Error::InvalidResponse("未找到直播间数据".to_string()))?;
+        let data: Value = serde_json::from_str(&caps[1])
+            .map_err(|e| Error::InvalidResponse(e.to_string()))?;
+
+        let live_stream = data.pointer("/liveroom/liveStream").ok_or_else(|| {
+            log::debug!("room {} has no liveStream", room_id);
+            Error::Offline
+        })?;
+
+        let play_urls = live_stream["playUrls"]
+            .as_array()
+            .ok_or_else(|| Error::InvalidResponse("缺少 playUrls 字段".to_string()))?;
+
+        let urls = play_urls
+            .iter()
+            .filter_map(|u| u["url"].as_str().map(str::to_string))
+            .collect::<Vec<String>>();
+
+        if urls.is_empty() {
+            return Err(Error::Offline);
+        }
+        Ok(urls)
+    }
+
+    fn matches_url(url: &str) -> Option<String> {
+        let re = Regex::new(r"live\.kuaishou\.com/u/([A-Za-z0-9_]+)").unwrap();
+        let caps = re.captures(url.trim())?;
+        Some(caps.get(1)?.as_str().to_string())
+    }
+}
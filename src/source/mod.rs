@@ -0,0 +1,124 @@
+mod bilibili;
+mod douyin;
+mod douyu;
+mod huya;
+mod kuaishou;
+
+use crate::error::Error;
+use crate::{Codec, Protocol, Quality};
+use async_trait::async_trait;
+
+pub use bilibili::Bilibili;
+pub use douyin::Douyin;
+pub use douyu::Douyu;
+pub use huya::Huya;
+pub use kuaishou::Kuaishou;
+
+/// A live-streaming platform that can resolve a room id into playable stream urls.
+#[async_trait]
+pub trait LiveSource {
+    /// Fetches the playable stream urls for `room_id` at the given `quality`/`codec`/`protocol`.
+    async fn fetch(
+        &self,
+        room_id: &str,
+        quality: Quality,
+        codec: Codec,
+        protocol: Protocol,
+    ) -> Result<Vec<String>, Error>;
+
+    /// Lists the quality tiers this room actually advertises, so the caller can present
+    /// only the choices that exist instead of a fixed Low/High pair. Platforms that don't
+    /// expose this matrix fall back to the two extremes.
+    async fn accept_qualities(&self, _room_id: &str) -> Result<Vec<Quality>, Error> {
+        Ok(vec![Quality::Fluent, Quality::Original])
+    }
+
+    /// Extracts a room id from a pasted url, if this source recognizes it.
+    fn matches_url(url: &str) -> Option<String>
+    where
+        Self: Sized;
+}
+
+/// Enum dispatch over every supported platform, so callers don't need a trait object.
+pub enum Platform {
+    Bilibili(Bilibili),
+    Douyu(Douyu),
+    Huya(Huya),
+    Douyin(Douyin),
+    Kuaishou(Kuaishou),
+}
+
+impl Platform {
+    /// Detects which platform a pasted url (or bare room id) belongs to, returning the
+    /// platform and the extracted room id. Bare numeric input defaults to Bilibili, to
+    /// preserve the previous behaviour of `read_room_id`.
+    pub fn detect(input: &str) -> Option<(Self, String)> {
+        if let Some(id) = Douyu::matches_url(input) {
+            return Some((Self::Douyu(Douyu), id));
+        }
+        if let Some(id) = Huya::matches_url(input) {
+            return Some((Self::Huya(Huya), id));
+        }
+        if let Some(id) = Douyin::matches_url(input) {
+            return Some((Self::Douyin(Douyin), id));
+        }
+        if let Some(id) = Kuaishou::matches_url(input) {
+            return Some((Self::Kuaishou(Kuaishou), id));
+        }
+        if let Some(id) = Bilibili::matches_url(input) {
+            return Some((Self::Bilibili(Bilibili), id));
+        }
+        None
+    }
+
+    /// A stable identifier for this platform, used to persist it alongside a room id so
+    /// a saved default can be reconstructed without re-running `detect` on an id that's
+    /// already had its platform stripped off.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Bilibili(_) => "bilibili",
+            Self::Douyu(_) => "douyu",
+            Self::Huya(_) => "huya",
+            Self::Douyin(_) => "douyin",
+            Self::Kuaishou(_) => "kuaishou",
+        }
+    }
+
+    /// Reconstructs a platform from the identifier returned by `key`.
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "bilibili" => Some(Self::Bilibili(Bilibili)),
+            "douyu" => Some(Self::Douyu(Douyu)),
+            "huya" => Some(Self::Huya(Huya)),
+            "douyin" => Some(Self::Douyin(Douyin)),
+            "kuaishou" => Some(Self::Kuaishou(Kuaishou)),
+            _ => None,
+        }
+    }
+
+    pub async fn fetch(
+        &self,
+        room_id: &str,
+        quality: Quality,
+        codec: Codec,
+        protocol: Protocol,
+    ) -> Result<Vec<String>, Error> {
+        match self {
+            Self::Bilibili(s) => s.fetch(room_id, quality, codec, protocol).await,
+            Self::Douyu(s) => s.fetch(room_id, quality, codec, protocol).await,
+            Self::Huya(s) => s.fetch(room_id, quality, codec, protocol).await,
+            Self::Douyin(s) => s.fetch(room_id, quality, codec, protocol).await,
+            Self::Kuaishou(s) => s.fetch(room_id, quality, codec, protocol).await,
+        }
+    }
+
+    pub async fn accept_qualities(&self, room_id: &str) -> Result<Vec<Quality>, Error> {
+        match self {
+            Self::Bilibili(s) => s.accept_qualities(room_id).await,
+            Self::Douyu(s) => s.accept_qualities(room_id).await,
+            Self::Huya(s) => s.accept_qualities(room_id).await,
+            Self::Douyin(s) => s.accept_qualities(room_id).await,
+            Self::Kuaishou(s) => s.accept_qualities(room_id).await,
+        }
+    }
+}